@@ -0,0 +1,173 @@
+//! Determinized Monte Carlo Tree Search for the hidden-information variant
+//! of the game: a `PovField` hides which `RPS` an opponent's unit holds
+//! until it has fought and become `visible`. Each iteration guesses a
+//! concrete board consistent with what is known, then searches it like a
+//! game of perfect information, sharing one tree across all guesses.
+
+use std::collections::HashMap;
+
+use rand::{self, Rng};
+
+use {Game, Player, Rules, RPS, WIDTH, HEIGHT};
+use move_conditions::{MoveCondition, Move};
+use win_conditions::WinCondition;
+use field::{Field, PovField};
+use unit::GeneralUnit;
+
+const EXPLORATION: f64 = 1.41;
+const ROLLOUT_TURN_CAP: u32 = 200;
+
+struct Node {
+    visits: u32,
+    wins: u32,
+    children: HashMap<Move, Node>,
+}
+
+impl Node {
+    fn new() -> Node {
+        Node { visits: 0, wins: 0, children: HashMap::new() }
+    }
+
+    fn uct(&self, parent_visits: u32) -> f64 {
+        if self.visits == 0 { return f64::INFINITY; }
+        let exploitation = self.wins as f64 / self.visits as f64;
+        let exploration = EXPLORATION * ((parent_visits as f64).ln() / self.visits as f64).sqrt();
+        exploitation + exploration
+    }
+}
+
+/// Runs `budget` determinized MCTS iterations from `player`'s point of view
+/// and returns the root child with the most visits.
+pub fn mcts_choose_move<T, E>(
+    pov: &PovField,
+    player: Player,
+    rules: &Rules<GeneralUnit, T, E>,
+    budget: usize,
+) -> Move
+    where T: MoveCondition + Clone, E: WinCondition<GeneralUnit> + Clone
+{
+    let mut root = Node::new();
+
+    for _ in 0..budget {
+        let mut game = determinize(pov, player, rules.clone());
+        simulate(&mut root, &mut game, player);
+    }
+
+    root.children
+        .into_iter()
+        .max_by_key(|entry| entry.1.visits)
+        .map(|(mv, _)| mv)
+        .expect("legal_moves() must not be empty on player's turn")
+}
+
+/// Fills every hidden enemy unit with a random `RPS` consistent with what
+/// the viewing player already knows, producing one concrete `Game`.
+fn determinize<T, E>(pov: &PovField, current_turn: Player, rules: Rules<GeneralUnit, T, E>) -> Game<T, E>
+    where T: MoveCondition, E: WinCondition<GeneralUnit>
+{
+    let mut rng = rand::thread_rng();
+    let mut rows = [[None; WIDTH]; HEIGHT];
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            rows[y][x] = pov.rows[y][x].map(|pov_unit| {
+                let rps = pov_unit.rps.unwrap_or_else(|| RPS::random_from(&mut rng));
+                GeneralUnit { rps: rps, owner: pov_unit.owner, visible: pov_unit.visible }
+            });
+        }
+    }
+
+    Game::from_parts(rules, Field { rows: rows }, current_turn, 0)
+}
+
+/// One determinization's worth of selection (by UCT) and expansion (one
+/// unvisited legal move), then a random rollout and backpropagation of its
+/// result along the path just walked.
+fn simulate<T, E>(root: &mut Node, game: &mut Game<T, E>, root_player: Player)
+    where T: MoveCondition, E: WinCondition<GeneralUnit>
+{
+    let mut path: Vec<Move> = Vec::new();
+    let mut node = &mut *root;
+
+    loop {
+        if game.winner().is_some() { break; }
+
+        let legal = game.legal_moves();
+        if legal.is_empty() { break; }
+
+        for mv in legal.iter().cloned() {
+            node.children.entry(mv).or_insert_with(Node::new);
+        }
+
+        let unvisited = legal.iter().cloned().find(|mv| node.children[mv].visits == 0);
+        let chosen = match unvisited {
+            Some(mv) => mv,
+            None => {
+                let parent_visits = node.visits.max(1);
+                legal.iter().cloned()
+                    .max_by(|a, b| {
+                        node.children[a].uct(parent_visits)
+                            .partial_cmp(&node.children[b].uct(parent_visits))
+                            .unwrap()
+                    })
+                    .unwrap()
+            }
+        };
+
+        game.make_move(chosen).expect("move came from legal_moves");
+        path.push(chosen);
+
+        let expanding = node.children[&chosen].visits == 0;
+        node = node.children.get_mut(&chosen).unwrap();
+        if expanding { break; }
+    }
+
+    let winner = rollout(game);
+
+    root.visits += 1;
+    if winner == Some(root_player) { root.wins += 1; }
+
+    let mut cursor = &mut *root;
+    for mv in &path {
+        let child = cursor.children.get_mut(mv).unwrap();
+        child.visits += 1;
+        if winner == Some(root_player) { child.wins += 1; }
+        cursor = child;
+    }
+}
+
+/// Plays uniformly random legal moves until someone wins or the turn cap
+/// is hit, in which case the rollout is scored as a draw (`None`).
+fn rollout<T, E>(game: &mut Game<T, E>) -> Option<Player>
+    where T: MoveCondition, E: WinCondition<GeneralUnit>
+{
+    let mut rng = rand::thread_rng();
+    let mut turns = 0;
+    loop {
+        if let Some(winner) = game.winner() { return Some(winner); }
+        if turns >= ROLLOUT_TURN_CAP { return None; }
+
+        let legal = game.legal_moves();
+        if legal.is_empty() { return None; }
+
+        let mv = legal[rng.gen::<usize>() % legal.len()];
+        game.make_move(mv).expect("move came from legal_moves");
+        turns += 1;
+    }
+}
+
+#[test]
+fn mcts_returns_a_legal_move() {
+    use Rules;
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+    use Game;
+
+    let game = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 42);
+    let legal = game.legal_moves();
+    let pov = game.perspective(game.current_turn());
+
+    let rules = Rules::new(OnlyForwardMove, EliminateCondition);
+    let mv = mcts_choose_move(&pov, game.current_turn(), &rules, 16);
+
+    assert!(legal.contains(&mv));
+}
@@ -0,0 +1,40 @@
+use {WIDTH, HEIGHT, Player, RPS};
+use unit::GeneralUnit;
+
+#[derive(Clone, Debug)]
+pub struct Field<T> {
+    pub rows: [[Option<T>; WIDTH]; HEIGHT],
+}
+
+/// One occupied square of a `PovField`, the `Field` as seen by one `Player`:
+/// own units are shown in full, but an opponent's unit only reveals its
+/// `RPS` choice once it has become `visible` through combat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PovUnit {
+    pub owner: Player,
+    pub visible: bool,
+    pub rps: Option<RPS>,
+}
+
+pub struct PovField {
+    pub rows: [[Option<PovUnit>; WIDTH]; HEIGHT],
+}
+
+impl<'a> From<(&'a Field<GeneralUnit>, Player)> for PovField {
+    fn from((field, viewer): (&'a Field<GeneralUnit>, Player)) -> PovField {
+        let mut rows = [[None; WIDTH]; HEIGHT];
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                rows[y][x] = field.rows[y][x].map(|unit| {
+                    let known = unit.owner == viewer || unit.visible;
+                    PovUnit {
+                        owner: unit.owner,
+                        visible: unit.visible,
+                        rps: if known { Some(unit.rps) } else { None },
+                    }
+                });
+            }
+        }
+        PovField { rows: rows }
+    }
+}
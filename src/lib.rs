@@ -1,6 +1,9 @@
 extern crate rand;
 extern crate rustc_serialize;
 
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
 /// Width of game board
 #[cfg(not(test))] pub const WIDTH: usize = 8;
 #[cfg(test)]      pub const WIDTH: usize = 3;
@@ -26,11 +29,17 @@ pub mod move_conditions;
 pub mod win_conditions;
 pub mod unit;
 pub mod field;
+pub mod ai;
+pub mod record;
+pub mod lobby;
+pub mod mode;
 
-use move_conditions::{MoveCondition, Move};
+use move_conditions::{MoveCondition, Move, Direction};
 use win_conditions::{WinCondition};
 use field::{Field, PovField};
 use unit::{Unit, GeneralUnit};
+use record::{GameRecord, RulesId};
+use mode::{GameMode, Difficulty};
 
 use std::marker::PhantomData;
 
@@ -41,25 +50,66 @@ pub struct Game<T: MoveCondition, E: WinCondition<GeneralUnit>> {
     winner: Option<Player>,
     field: Field<GeneralUnit>,
     rules: Rules<GeneralUnit, T, E>,
+    seed: u64,
+    history: GameRecord,
+    mode: GameMode,
 }
 
 impl<T: MoveCondition, E: WinCondition<GeneralUnit>> Game<T, E> {
     pub fn new(rules: Rules<GeneralUnit, T, E>) -> Game<T, E> {
+        let seed = rand::random::<u64>();
+        Game::new_seeded(rules, seed)
+    }
+
+    /// Builds the same starting position `new` would, but draws every random
+    /// unit from a `StdRng` seeded with `seed` instead of the thread-global
+    /// RNG, so `(seed, rules)` plus the move list fully determines the game.
+    pub fn new_seeded(rules: Rules<GeneralUnit, T, E>, seed: u64) -> Game<T, E> {
+        let mut rng = StdRng::seed_from_u64(seed);
         let mut rows = [[None; WIDTH]; HEIGHT];
         for i in 0..ROWS {
-            rows[i] = [Some(RED.random_unit()); WIDTH];
-            rows[HEIGHT - i - 1] = [Some(BLUE.random_unit()); WIDTH];
+            rows[i] = [Some(RED.random_unit_from(&mut rng)); WIDTH];
+            rows[HEIGHT - i - 1] = [Some(BLUE.random_unit_from(&mut rng)); WIDTH];
         }
         let field = Field { rows: rows };
+        Game::from_parts(rules, field, RED, seed)
+    }
+
+    /// Assembles a `Game` from an already-built board, for any caller that
+    /// needs a concrete starting position other than the one `new_seeded`
+    /// deals (determinized MCTS, and tests that hand-place a board). Kept as the one
+    /// place that lists every field, so adding a field to `Game` is a
+    /// one-line change here instead of a struct literal to track down in
+    /// every module that builds one.
+    pub(crate) fn from_parts(
+        rules: Rules<GeneralUnit, T, E>,
+        field: Field<GeneralUnit>,
+        current_turn: Player,
+        seed: u64,
+    ) -> Game<T, E> {
         Game {
             turns: 1,
-            current_turn: RED,
+            current_turn: current_turn,
             winner: None,
             field: field,
             rules: rules,
+            seed: seed,
+            // Only one concrete `Rules` combination exists in this crate so
+            // far; `RulesId` will need a real mapping once a second one does.
+            history: GameRecord::new(seed, RulesId::Standard),
+            mode: GameMode::LocalMultiplayer,
         }
     }
-    
+
+    pub fn seed(&self) -> u64 { self.seed }
+
+    /// The moves played so far, annotatable and replayable independently
+    /// of this `Game`'s trait-typed `Rules`.
+    pub fn history(&self) -> &GameRecord { &self.history }
+
+    pub fn mode(&self) -> GameMode { self.mode }
+    pub fn set_mode(&mut self, mode: GameMode) { self.mode = mode; }
+
     pub fn turns(&self) -> u32 { self.turns }
     pub fn current_turn(&self) -> Player { self.current_turn }
     pub fn winner(&self) -> Option<Player> { self.winner }
@@ -73,47 +123,75 @@ impl<T: MoveCondition, E: WinCondition<GeneralUnit>> Game<T, E> {
         PovField::from((&self.field, player))
     }
     
-    pub fn make_move(&mut self, movement: Move) -> Result<Option<Outcome>, MoveError> {
+    /// Checks whether `movement` can legally be played right now, without
+    /// mutating `self.field`. Returns the target square and the `Outcome`
+    /// an attack there would produce, so callers can inspect a move before
+    /// committing to it.
+    pub fn validate_move(&self, movement: Move) -> Result<ResolvedMove, MoveError> {
         if self.winner.is_some() { return Err(MoveError::GameAlreadyFinished); }
-        
+
         if !self.rules.move_condition.is_valid(movement) {
             return Err(MoveError::DeclinedByMoveCondition);
         }
-        
+
         let (from_x, from_y) = movement.from;
-        
+
         if from_x >= WIDTH || from_y >= HEIGHT { return Err(MoveError::PositionOutOfBounds); }
-        
-        let attack_outcome;
-        let (to_x, to_y);
-        
+
         if let Some(ref unit) = self.field.rows[from_y][from_x].as_ref() {
             if unit.owner != self.current_turn { return Err(MoveError::WrongOwner); }
-            let dist = movement.apply(unit.owner);
-            to_x = dist.0;
-            to_y = dist.1; 
+            let (to_x, to_y) = movement.apply(unit.owner);
             if to_x >= WIDTH || to_y >= HEIGHT { return Err(MoveError::PositionOutOfBounds); }
-            
+
             if let Some(ref defender) = self.field.rows[to_y][to_x].as_ref() {
                 if defender.owner == self.current_turn { return Err(MoveError::SameOwner); }
-                
+
                 match unit.attack(defender) {
-                    Some(res) => {
-                        attack_outcome = Some(res);
-                    },
-                    None => { return Err(MoveError::UnexpextedError); }
-                } 
+                    Some(res) => Ok(ResolvedMove { to: (to_x, to_y), outcome: Some(res) }),
+                    None => Err(MoveError::UnexpextedError),
+                }
             } else {
-                attack_outcome = None;
+                Ok(ResolvedMove { to: (to_x, to_y), outcome: None })
             }
-            
-            
-            
         } else {
-            return Err(MoveError::NoUnitInPosition);
+            Err(MoveError::NoUnitInPosition)
         }
-        
-        if let Some(outcome) = attack_outcome {
+    }
+
+    /// Every move the current player could legally play, found by probing
+    /// `validate_move` with each `Direction` from each of their own units.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let owned = match self.field.rows[y][x].as_ref() {
+                    Some(unit) => unit.owner == self.current_turn,
+                    None => false,
+                };
+                if !owned { continue; }
+
+                for &direction in Direction::all().iter() {
+                    let candidate = Move::new(x, y, direction);
+                    if self.validate_move(candidate).is_ok() {
+                        moves.push(candidate);
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    pub fn make_move(&mut self, movement: Move) -> Result<Option<Outcome>, MoveError> {
+        let resolved = self.validate_move(movement)?;
+        self.apply_resolved(movement, resolved);
+        Ok(resolved.outcome)
+    }
+
+    fn apply_resolved(&mut self, movement: Move, resolved: ResolvedMove) {
+        let (from_x, from_y) = movement.from;
+        let (to_x, to_y) = resolved.to;
+
+        if let Some(outcome) = resolved.outcome {
             match outcome {
                 WIN => {
                     self.field.rows[to_y][to_x] = self.field.rows[from_y][from_x];
@@ -133,15 +211,63 @@ impl<T: MoveCondition, E: WinCondition<GeneralUnit>> Game<T, E> {
             self.field.rows[to_y][to_x] = self.field.rows[from_y][from_x];
             self.field.rows[from_y][from_x] = None;
         }
-        
+
+        self.history.push(movement, resolved.outcome, self.current_turn);
         self.winner = self.rules.win_condition.winner(&self.field);
         self.turns += 1;
         self.current_turn = self.current_turn.next();
-        
-        Ok(attack_outcome)
     }
 }
 
+const HARD_MCTS_BUDGET: usize = 1000;
+
+impl<T: MoveCondition + Clone, E: WinCondition<GeneralUnit> + Clone> Game<T, E> {
+    /// If it is the configured AI side's turn in `SinglePlayer` mode,
+    /// selects and applies a move according to `Difficulty` and returns
+    /// what was played. Otherwise does nothing, so a front end can call
+    /// this unconditionally after every turn without checking the mode
+    /// itself.
+    pub fn step_ai(&mut self) -> Option<(Move, Option<Outcome>)> {
+        if self.winner.is_some() { return None; }
+
+        let (ai_side, difficulty) = match self.mode {
+            GameMode::SinglePlayer { ai_side, difficulty } if ai_side == self.current_turn => {
+                (ai_side, difficulty)
+            },
+            _ => return None,
+        };
+
+        let legal = self.legal_moves();
+        if legal.is_empty() { return None; }
+
+        let chosen = match difficulty {
+            Difficulty::Easy => legal[rand::thread_rng().gen::<usize>() % legal.len()],
+            Difficulty::Medium => {
+                let winning: Vec<Move> = legal.iter().cloned()
+                    .filter(|&mv| self.validate_move(mv).map(|r| r.outcome == Some(WIN)).unwrap_or(false))
+                    .collect();
+                let pool = if winning.is_empty() { &legal } else { &winning };
+                pool[rand::thread_rng().gen::<usize>() % pool.len()]
+            },
+            Difficulty::Hard => {
+                let pov = self.perspective(ai_side);
+                ai::mcts_choose_move(&pov, ai_side, &self.rules, HARD_MCTS_BUDGET)
+            },
+        };
+
+        let outcome = self.make_move(chosen).expect("step_ai only selects from legal_moves()");
+        Some((chosen, outcome))
+    }
+}
+
+/// The target square and resulting `Outcome` a `Move` would produce, as
+/// computed by `Game::validate_move` without touching the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolvedMove {
+    pub to: (usize, usize),
+    pub outcome: Option<Outcome>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MoveError {
     GameAlreadyFinished,
@@ -172,8 +298,8 @@ impl Player {
         GeneralUnit::new(rps, *self)
     }
     
-    fn random_unit(&self) -> GeneralUnit {
-        self.unit(RPS::random())
+    fn random_unit_from<R: Rng>(&self, rng: &mut R) -> GeneralUnit {
+        self.unit(RPS::random_from(rng))
     }
 }
 
@@ -193,17 +319,17 @@ impl RPS {
         }
     }
     
-    fn random() -> RPS {
-        match rand::random::<usize>() % 3 {
+    fn random_from<R: Rng>(rng: &mut R) -> RPS {
+        match rng.gen::<usize>() % 3 {
             0 => ROCK,
             1 => PAPER,
             2 => SCISSORS,
-            _ => { panic!("rand::random::<usize>() % 3 returned not 0, nor 1, nor 2"); }
+            _ => { panic!("rng.gen::<usize>() % 3 returned not 0, nor 1, nor 2"); }
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
 pub enum Outcome {
     Win,
     Lose,
@@ -243,3 +369,89 @@ fn basic_test() {
     let move2 = Move::new(0, 2, Direction::Forward);
     assert!(game.make_move(move2).unwrap().is_some());
 }
+
+#[test]
+fn new_seeded_is_deterministic() {
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+
+    let game_a = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 7);
+    let game_b = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 7);
+
+    assert_eq!(format!("{:?}", game_a.field()), format!("{:?}", game_b.field()));
+}
+
+#[test]
+fn step_ai_does_nothing_outside_single_player_or_off_turn() {
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+
+    let mut game = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 1);
+    assert_eq!(game.step_ai(), None);
+
+    game.set_mode(GameMode::SinglePlayer { ai_side: BLUE, difficulty: Difficulty::Easy });
+    assert_eq!(game.current_turn(), RED);
+    assert_eq!(game.step_ai(), None);
+}
+
+#[test]
+fn step_ai_easy_plays_a_move_that_was_legal() {
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+
+    let mut game = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 1);
+    let legal_before = game.legal_moves();
+    game.set_mode(GameMode::SinglePlayer { ai_side: RED, difficulty: Difficulty::Easy });
+
+    let (mv, _) = game.step_ai().expect("it is RED's turn and legal moves exist");
+    assert!(legal_before.contains(&mv));
+}
+
+#[test]
+fn step_ai_medium_prefers_a_winning_attack_when_one_exists() {
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+
+    // A hand-placed board where RED's only legal move is a Rock-over-Scissors
+    // attack, so Medium's "prefer a WIN" filter has exactly one move to pick.
+    let mut rows = [[None; WIDTH]; HEIGHT];
+    rows[0][0] = Some(GeneralUnit::new(ROCK, RED));
+    rows[1][0] = Some(GeneralUnit::new(SCISSORS, BLUE));
+    let field = Field { rows: rows };
+
+    let mut game = Game::from_parts(Rules::new(OnlyForwardMove, EliminateCondition), field, RED, 1);
+    game.set_mode(GameMode::SinglePlayer { ai_side: RED, difficulty: Difficulty::Medium });
+
+    let (mv, outcome) = game.step_ai().expect("RED has one legal move, a winning attack");
+
+    assert_eq!(mv, Move::new(0, 0, Direction::Forward));
+    assert_eq!(outcome, Some(WIN));
+}
+
+#[test]
+fn step_ai_hard_plays_a_move_that_was_legal() {
+    use move_conditions::OnlyForwardMove;
+    use win_conditions::EliminateCondition;
+
+    let mut game = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 1);
+    let legal_before = game.legal_moves();
+    game.set_mode(GameMode::SinglePlayer { ai_side: RED, difficulty: Difficulty::Hard });
+
+    let (mv, _) = game.step_ai().expect("it is RED's turn and legal moves exist");
+    assert!(legal_before.contains(&mv));
+}
+
+#[test]
+fn legal_moves_are_all_validate_move_approved() {
+    use move_conditions::{OnlyForwardMove, Direction};
+    use win_conditions::EliminateCondition;
+
+    let game = Game::new_seeded(Rules::new(OnlyForwardMove, EliminateCondition), 1);
+    let legal = game.legal_moves();
+
+    assert_eq!(legal.len(), WIDTH);
+    for mv in &legal {
+        assert_eq!(mv.direction, Direction::Forward);
+        assert!(game.validate_move(*mv).is_ok());
+    }
+}
@@ -0,0 +1,141 @@
+//! A create -> join -> accept handshake for a networked match: one player
+//! creates a `Match` and waits, a second joins and the host accepts,
+//! dealing the board and starting play. Unlike `Game`, `Match` carries no
+//! trait-typed `Rules`, only a `RulesId` and a `GameRecord` snapshot, so the
+//! whole thing can be shipped to the other peer after every move.
+
+use rand;
+
+use {Player, Outcome, MoveError};
+use move_conditions::Move;
+use record::{GameRecord, RulesId};
+use Game;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum MatchState {
+    WaitingForOpponent,
+    JoinRequested,
+    InProgress,
+    Finished(Player),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchError {
+    WrongState(MatchState),
+    SameOwner,
+    Move(MoveError),
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct Match {
+    // `state`/`host`/`guest`/`rules` are plain data and `record` is a
+    // `GameRecord` snapshot, so the whole struct can be shipped to the
+    // other peer after every `make_move`.
+    state: MatchState,
+    host: Player,
+    guest: Option<Player>,
+    rules: RulesId,
+    record: Option<GameRecord>,
+}
+
+impl Match {
+    pub fn create(rules: RulesId, host: Player) -> Match {
+        Match {
+            state: MatchState::WaitingForOpponent,
+            host: host,
+            guest: None,
+            rules: rules,
+            record: None,
+        }
+    }
+
+    pub fn state(&self) -> MatchState { self.state }
+    pub fn host(&self) -> Player { self.host }
+    pub fn guest(&self) -> Option<Player> { self.guest }
+    pub fn record(&self) -> Option<&GameRecord> { self.record.as_ref() }
+
+    pub fn join(&mut self, guest: Player) -> Result<(), MatchError> {
+        if self.state != MatchState::WaitingForOpponent {
+            return Err(MatchError::WrongState(self.state));
+        }
+        if guest == self.host {
+            return Err(MatchError::SameOwner);
+        }
+        self.guest = Some(guest);
+        self.state = MatchState::JoinRequested;
+        Ok(())
+    }
+
+    /// Deals the board and starts play, seeding a fresh `Game` whose
+    /// history becomes this match's first `GameRecord` snapshot.
+    pub fn accept(&mut self) -> Result<(), MatchError> {
+        if self.state != MatchState::JoinRequested {
+            return Err(MatchError::WrongState(self.state));
+        }
+        let seed = rand::random::<u64>();
+        let game = Game::new_seeded(self.rules.rules(), seed);
+        self.record = Some(game.history().clone());
+        self.state = MatchState::InProgress;
+        Ok(())
+    }
+
+    /// Replays the current snapshot, applies `movement` to it, and stores
+    /// the resulting history back as the new snapshot.
+    pub fn make_move(&mut self, movement: Move) -> Result<Option<Outcome>, MatchError> {
+        if self.state != MatchState::InProgress {
+            return Err(MatchError::WrongState(self.state));
+        }
+
+        let mut game = self.record.as_ref()
+            .expect("InProgress implies accept() has set a record")
+            .replay();
+
+        let outcome = game.make_move(movement).map_err(MatchError::Move)?;
+
+        if let Some(winner) = game.winner() {
+            self.state = MatchState::Finished(winner);
+        }
+        self.record = Some(game.history().clone());
+
+        Ok(outcome)
+    }
+}
+
+#[test]
+fn lifecycle_goes_through_the_handshake_in_order() {
+    let mut m = Match::create(RulesId::Standard, Player::Red);
+    assert_eq!(m.state(), MatchState::WaitingForOpponent);
+
+    assert_eq!(m.accept(), Err(MatchError::WrongState(MatchState::WaitingForOpponent)));
+
+    m.join(Player::Blue).unwrap();
+    assert_eq!(m.state(), MatchState::JoinRequested);
+    assert_eq!(m.guest(), Some(Player::Blue));
+
+    m.accept().unwrap();
+    assert_eq!(m.state(), MatchState::InProgress);
+    assert!(m.record().is_some());
+}
+
+#[test]
+fn join_rejects_a_guest_equal_to_the_host() {
+    let mut m = Match::create(RulesId::Standard, Player::Red);
+    assert_eq!(m.join(Player::Red), Err(MatchError::SameOwner));
+    assert_eq!(m.state(), MatchState::WaitingForOpponent);
+}
+
+#[test]
+fn make_move_is_rejected_unless_in_progress() {
+    use move_conditions::Direction;
+
+    let mut m = Match::create(RulesId::Standard, Player::Red);
+    let mv = Move::new(0, 0, Direction::Forward);
+
+    assert_eq!(m.make_move(mv), Err(MatchError::WrongState(MatchState::WaitingForOpponent)));
+
+    m.join(Player::Blue).unwrap();
+    assert_eq!(m.make_move(mv), Err(MatchError::WrongState(MatchState::JoinRequested)));
+
+    m.accept().unwrap();
+    assert!(m.make_move(mv).is_ok());
+}
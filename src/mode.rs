@@ -0,0 +1,20 @@
+use Player;
+
+/// How strong an AI opponent plays, from `Game::step_ai`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    /// A uniformly random legal move.
+    Easy,
+    /// A one-ply greedy search that prefers attacks that win.
+    Medium,
+    /// Determinized MCTS with a large iteration budget.
+    Hard,
+}
+
+/// Who is driving each side of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    LocalMultiplayer,
+    SinglePlayer { ai_side: Player, difficulty: Difficulty },
+    Networked,
+}
@@ -0,0 +1,58 @@
+use Player;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub enum Direction {
+    Forward,
+    Backward,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [Direction::Forward, Direction::Backward, Direction::Left, Direction::Right]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub struct Move {
+    pub from: (usize, usize),
+    pub direction: Direction,
+}
+
+impl Move {
+    pub fn new(x: usize, y: usize, direction: Direction) -> Move {
+        Move { from: (x, y), direction: direction }
+    }
+
+    /// Resolves the target square for this move, given which player owns
+    /// the moving unit: `Forward`/`Backward` are relative to that player's
+    /// side of the board, so Red and Blue advance towards each other.
+    pub fn apply(&self, owner: Player) -> (usize, usize) {
+        let (x, y) = self.from;
+        match (self.direction, owner) {
+            (Direction::Forward, Player::Red) => (x, y.wrapping_add(1)),
+            (Direction::Forward, Player::Blue) => (x, y.wrapping_sub(1)),
+            (Direction::Backward, Player::Red) => (x, y.wrapping_sub(1)),
+            (Direction::Backward, Player::Blue) => (x, y.wrapping_add(1)),
+            (Direction::Left, Player::Red) => (x.wrapping_sub(1), y),
+            (Direction::Left, Player::Blue) => (x.wrapping_add(1), y),
+            (Direction::Right, Player::Red) => (x.wrapping_add(1), y),
+            (Direction::Right, Player::Blue) => (x.wrapping_sub(1), y),
+        }
+    }
+}
+
+pub trait MoveCondition {
+    fn is_valid(&self, movement: Move) -> bool;
+}
+
+/// Units may only ever step towards the opponent's side of the board.
+#[derive(Clone, Copy, Debug)]
+pub struct OnlyForwardMove;
+
+impl MoveCondition for OnlyForwardMove {
+    fn is_valid(&self, movement: Move) -> bool {
+        movement.direction == Direction::Forward
+    }
+}
@@ -0,0 +1,105 @@
+//! Recordable, replayable game history, in the spirit of how an SGF file
+//! captures a Go game as a move list plus comments and position
+//! evaluations. A `Game` cannot itself be `RustcEncodable` because its
+//! `Rules` holds trait-typed conditions, so a `GameRecord` stores the
+//! starting seed and a serializable `RulesId` instead, and can rebuild the
+//! exact game by replaying its moves onto a freshly seeded board.
+
+use {Game, Player, Outcome, Rules};
+use move_conditions::{Move, OnlyForwardMove};
+use win_conditions::EliminateCondition;
+use unit::GeneralUnit;
+
+/// Identifies which concrete `Rules` a game was played under. This crate
+/// only ever constructs one combination of conditions; a second one would
+/// need its own variant here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum RulesId {
+    Standard,
+}
+
+impl RulesId {
+    pub(crate) fn rules(&self) -> Rules<GeneralUnit, OnlyForwardMove, EliminateCondition> {
+        match *self {
+            RulesId::Standard => Rules::new(OnlyForwardMove, EliminateCondition),
+        }
+    }
+}
+
+/// A post-game judgement of a position, for annotating a `GameRecord` the
+/// way a reviewer marks up an SGF.
+#[derive(Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum Evaluation {
+    Even,
+    GoodForRed,
+    GoodForBlue,
+    Unclear,
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct MoveEntry {
+    pub movement: Move,
+    pub outcome: Option<Outcome>,
+    pub player: Player,
+    pub comment: Option<String>,
+    pub evaluation: Option<Evaluation>,
+}
+
+impl MoveEntry {
+    fn new(movement: Move, outcome: Option<Outcome>, player: Player) -> MoveEntry {
+        MoveEntry { movement: movement, outcome: outcome, player: player, comment: None, evaluation: None }
+    }
+}
+
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct GameRecord {
+    seed: u64,
+    rules: RulesId,
+    moves: Vec<MoveEntry>,
+}
+
+impl GameRecord {
+    pub fn new(seed: u64, rules: RulesId) -> GameRecord {
+        GameRecord { seed: seed, rules: rules, moves: Vec::new() }
+    }
+
+    pub fn push(&mut self, movement: Move, outcome: Option<Outcome>, player: Player) {
+        self.moves.push(MoveEntry::new(movement, outcome, player));
+    }
+
+    pub fn moves(&self) -> &[MoveEntry] { &self.moves }
+
+    pub fn comment(&mut self, index: usize, comment: String) {
+        if let Some(entry) = self.moves.get_mut(index) { entry.comment = Some(comment); }
+    }
+
+    pub fn evaluate(&mut self, index: usize, evaluation: Evaluation) {
+        if let Some(entry) = self.moves.get_mut(index) { entry.evaluation = Some(evaluation); }
+    }
+
+    /// Rebuilds the exact game this record describes: a fresh board seeded
+    /// the same way the original was, with every recorded move replayed
+    /// onto it in order.
+    pub fn replay(&self) -> Game<OnlyForwardMove, EliminateCondition> {
+        let mut game = Game::new_seeded(self.rules.rules(), self.seed);
+        for entry in &self.moves {
+            game.make_move(entry.movement).expect("a recorded move was legal when first played");
+        }
+        game
+    }
+}
+
+#[test]
+fn replay_reproduces_the_original_game() {
+    use move_conditions::{Move, Direction};
+
+    let mut game = Game::new_seeded(RulesId::Standard.rules(), 3);
+    game.make_move(Move::new(0, 0, Direction::Forward)).unwrap();
+    game.make_move(Move::new(0, 2, Direction::Forward)).unwrap();
+
+    let replayed = game.history().replay();
+
+    assert_eq!(format!("{:?}", game.field()), format!("{:?}", replayed.field()));
+    assert_eq!(game.current_turn(), replayed.current_turn());
+    assert_eq!(game.turns(), replayed.turns());
+}
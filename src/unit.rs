@@ -0,0 +1,27 @@
+use {Player, RPS, Outcome};
+
+/// Anything that can occupy a square and be attacked on it.
+pub trait Unit: Copy {
+    fn owner(&self) -> Player;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct GeneralUnit {
+    pub rps: RPS,
+    pub owner: Player,
+    pub visible: bool,
+}
+
+impl GeneralUnit {
+    pub fn new(rps: RPS, owner: Player) -> GeneralUnit {
+        GeneralUnit { rps: rps, owner: owner, visible: false }
+    }
+
+    pub fn attack(&self, defender: &GeneralUnit) -> Option<Outcome> {
+        Some(self.rps.attack(defender.rps))
+    }
+}
+
+impl Unit for GeneralUnit {
+    fn owner(&self) -> Player { self.owner }
+}
@@ -0,0 +1,33 @@
+use Player;
+use field::Field;
+use unit::Unit;
+
+pub trait WinCondition<K: Unit> {
+    fn winner(&self, field: &Field<K>) -> Option<Player>;
+}
+
+/// A player wins as soon as the opponent has no units left on the board.
+#[derive(Clone, Copy, Debug)]
+pub struct EliminateCondition;
+
+impl<K: Unit> WinCondition<K> for EliminateCondition {
+    fn winner(&self, field: &Field<K>) -> Option<Player> {
+        let mut red_alive = false;
+        let mut blue_alive = false;
+        for row in field.rows.iter() {
+            for cell in row.iter() {
+                if let Some(ref unit) = *cell {
+                    match unit.owner() {
+                        Player::Red => red_alive = true,
+                        Player::Blue => blue_alive = true,
+                    }
+                }
+            }
+        }
+        match (red_alive, blue_alive) {
+            (true, false) => Some(Player::Red),
+            (false, true) => Some(Player::Blue),
+            _ => None,
+        }
+    }
+}